@@ -0,0 +1,182 @@
+/// Directory listing support for the srv HTTP server.
+///
+/// `ServeDir` alone 404s on any directory without an `index.html`. This
+/// module adds a handler that sits in front of `ServeDir`: for directories
+/// missing the configured index file it renders an HTML listing
+/// (directories first, then by name) using the existing
+/// `human_readable_size`/`get_modification_time` helpers; the table has no
+/// client-side column sorting. Everything else falls through to `ServeDir`
+/// unchanged.
+use std::cmp::Ordering;
+use std::path::Path;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use tower::ServiceExt;
+use tower_http::services::ServeDir;
+
+use crate::config::ServerConfig;
+use crate::utils::{get_modification_time, human_readable_size};
+use crate::writable::resolve_path;
+
+/// Shared state for the autoindex handler: the server config (for
+/// `show_hidden`/`follow_symlinks`/`index_file`) plus the `ServeDir`
+/// service to fall through to for actual files.
+#[derive(Clone)]
+pub struct AutoindexState {
+    pub config: ServerConfig,
+    pub serve_dir: ServeDir,
+}
+
+struct Entry {
+    name: String,
+    href: String,
+    is_dir: bool,
+    size: String,
+    modified: String,
+}
+
+/// Axum handler: render a directory listing, or fall through to `ServeDir`.
+pub async fn serve(State(state): State<AutoindexState>, request: Request<Body>) -> Response {
+    let uri_path = request.uri().path().to_string();
+
+    // Same normalize-and-`starts_with` guard `writable` uses, so a path
+    // like `/../../etc/` can't resolve outside `config.directory` and get
+    // listed — `ServeDir`'s own fallback already rejects `..` segments, and
+    // this handler sits in front of it.
+    let fs_path = match resolve_path(&state.config, &uri_path) {
+        Ok(path) => path,
+        Err(response) => return response,
+    };
+
+    if fs_path.is_dir() && !fs_path.join(&state.config.index_file).is_file() {
+        return match render_listing(&state.config, &fs_path, &uri_path) {
+            Ok(html) => Html(html).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list directory: {}", e)).into_response(),
+        };
+    }
+
+    match state.serve_dir.oneshot(request).await {
+        Ok(response) => response.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to serve file: {}", e)).into_response(),
+    }
+}
+
+/// Render an HTML directory listing for `dir`, honoring hidden-file and
+/// symlink settings from `config`.
+fn render_listing(config: &ServerConfig, dir: &Path, uri_path: &str) -> std::io::Result<String> {
+    let mut entries = Vec::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+
+        if !config.show_hidden && file_name.starts_with('.') {
+            continue;
+        }
+
+        let path = entry.path();
+        let metadata = if config.follow_symlinks {
+            std::fs::metadata(&path)
+        } else {
+            std::fs::symlink_metadata(&path)
+        };
+        let metadata = match metadata {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+
+        let is_dir = metadata.is_dir();
+        let size = if is_dir { "-".to_string() } else { human_readable_size(metadata.len()) };
+        let modified = get_modification_time(&path);
+        let encoded_name = utf8_percent_encode(&file_name, NON_ALPHANUMERIC).to_string();
+        let href = if is_dir { format!("{}/", encoded_name) } else { encoded_name };
+
+        entries.push(Entry { name: file_name, href, is_dir, size, modified });
+    }
+
+    // Directories first, then alphabetical within each group
+    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    });
+
+    let parent_row = if uri_path != "/" {
+        "<tr><td><a href=\"../\">../</a></td><td>-</td><td></td></tr>\n".to_string()
+    } else {
+        String::new()
+    };
+
+    let rows: String = entries
+        .iter()
+        .map(|e| {
+            format!(
+                "<tr><td><a href=\"{href}\">{name}{slash}</a></td><td>{size}</td><td>{modified}</td></tr>\n",
+                href = e.href,
+                name = html_escape(&e.name),
+                slash = if e.is_dir { "/" } else { "" },
+                size = e.size,
+                modified = e.modified,
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Index of {path}</title></head>\n\
+         <body>\n<h1>Index of {path}</h1>\n\
+         <table>\n<thead><tr><th>Name</th><th>Size</th><th>Modified</th></tr></thead>\n<tbody>\n{parent}{rows}</tbody>\n</table>\n</body>\n</html>\n",
+        path = html_escape(uri_path),
+        parent = parent_row,
+        rows = rows,
+    ))
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(html_escape("<b>&amp;</b>"), "&lt;b&gt;&amp;amp;&lt;/b&gt;");
+    }
+
+    #[test]
+    fn test_render_listing_hides_dotfiles_by_default() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("visible.txt"), b"hi").unwrap();
+        std::fs::write(dir.path().join(".hidden"), b"hi").unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+
+        let mut config = ServerConfig::new(8000, dir.path().to_str().unwrap());
+        config.resolve_directory().unwrap();
+
+        let html = render_listing(&config, dir.path(), "/").unwrap();
+        assert!(html.contains("visible.txt"));
+        assert!(html.contains("sub/"));
+        assert!(!html.contains(".hidden"));
+    }
+
+    #[test]
+    fn test_render_listing_shows_hidden_when_configured() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join(".hidden"), b"hi").unwrap();
+
+        let mut config = ServerConfig::new(8000, dir.path().to_str().unwrap());
+        config.resolve_directory().unwrap();
+        config.show_hidden = true;
+
+        let html = render_listing(&config, dir.path(), "/").unwrap();
+        assert!(html.contains(".hidden"));
+    }
+}
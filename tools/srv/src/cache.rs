@@ -0,0 +1,302 @@
+/// In-memory LRU cache for hot static files.
+///
+/// Sits in front of `ServeDir`/autoindex as a response layer: resolves the
+/// request path to a file under `config.directory` and either serves a
+/// cached copy (or a `304 Not Modified`) directly, or lets the request
+/// fall through and caches whatever comes back. Entries are keyed by
+/// resolved path, evicted by total byte size rather than entry count, and
+/// invalidated on mtime change so edits during development are picked up
+/// immediately.
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use lru::LruCache;
+use tracing::debug;
+
+use crate::config::ServerConfig;
+use crate::server::get_mime_type;
+
+/// Cap how large a single buffered response body can be before we give up
+/// and serve it uncached, so one huge file can't block on buffering it.
+const MAX_CACHEABLE_BODY: usize = 8 * 1024 * 1024;
+
+#[derive(Clone)]
+struct CacheEntry {
+    bytes: Bytes,
+    content_type: String,
+    etag: String,
+    last_modified: SystemTime,
+}
+
+struct Inner {
+    entries: LruCache<PathBuf, CacheEntry>,
+    total_bytes: usize,
+}
+
+/// Shared, cloneable handle to the cache. `max_bytes` of `0` disables it.
+#[derive(Clone)]
+pub struct FileCache {
+    inner: Arc<Mutex<Inner>>,
+    max_bytes: usize,
+}
+
+impl FileCache {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner { entries: LruCache::unbounded(), total_bytes: 0 })),
+            max_bytes,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_bytes > 0
+    }
+
+    /// Look up `path`, invalidating (and discarding) a stale entry whose
+    /// `mtime` no longer matches the file on disk.
+    fn get(&self, path: &PathBuf, mtime: SystemTime) -> Option<CacheEntry> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.entries.get(path) {
+            Some(entry) if entry.last_modified == mtime => Some(entry.clone()),
+            Some(_) => {
+                if let Some(stale) = inner.entries.pop(path) {
+                    inner.total_bytes = inner.total_bytes.saturating_sub(stale.bytes.len());
+                }
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Insert `entry`, evicting least-recently-used entries until the
+    /// cache fits back under `max_bytes`.
+    fn insert(&self, path: PathBuf, entry: CacheEntry) {
+        let size = entry.bytes.len();
+        if size > self.max_bytes {
+            return; // a single file too large to ever fit the budget
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(old) = inner.entries.put(path, entry) {
+            inner.total_bytes = inner.total_bytes.saturating_sub(old.bytes.len());
+        }
+        inner.total_bytes += size;
+
+        while inner.total_bytes > self.max_bytes {
+            match inner.entries.pop_lru() {
+                Some((_, evicted)) => inner.total_bytes = inner.total_bytes.saturating_sub(evicted.bytes.len()),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Shared state for the cache middleware: the cache itself plus the
+/// server config (for resolving the request path to a file on disk).
+#[derive(Clone)]
+pub struct CacheState {
+    pub config: ServerConfig,
+    pub cache: FileCache,
+}
+
+/// Middleware: serve a cache hit (or a `304`) directly, otherwise let the
+/// request through and cache the response it comes back with.
+pub async fn apply(State(state): State<CacheState>, req: Request, next: Next) -> Response {
+    if !state.cache.enabled() || req.method() != Method::GET {
+        return next.run(req).await;
+    }
+
+    let relative = req.uri().path().trim_start_matches('/').to_string();
+    let fs_path = state.config.directory.join(&relative);
+
+    let mtime = std::fs::metadata(&fs_path).and_then(|m| m.modified()).ok();
+    let Some(mtime) = mtime else {
+        return next.run(req).await;
+    };
+
+    if let Some(entry) = state.cache.get(&fs_path, mtime) {
+        if client_has_fresh_copy(&req, &entry) {
+            return StatusCode::NOT_MODIFIED.into_response();
+        }
+        return entry_response(entry);
+    }
+
+    let response = next.run(req).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    cache_response(&state.cache, fs_path, mtime, response).await
+}
+
+/// `If-None-Match` takes precedence over `If-Modified-Since` per RFC 7232,
+/// so only fall back to the timestamp comparison when the client didn't
+/// send an `ETag` to compare against.
+fn client_has_fresh_copy(req: &Request, entry: &CacheEntry) -> bool {
+    if let Some(if_none_match) = req.headers().get(header::IF_NONE_MATCH) {
+        return if_none_match.to_str().map(|v| v.trim() == entry.etag).unwrap_or(false);
+    }
+
+    if let Some(if_modified_since) = req.headers().get(header::IF_MODIFIED_SINCE) {
+        let since = if_modified_since.to_str().ok().and_then(|v| httpdate::parse_http_date(v).ok());
+        if let Some(since) = since {
+            // HTTP-dates only carry second resolution; truncate both sides
+            // before comparing so a file with sub-second mtime precision
+            // doesn't spuriously compare as "modified" within the same second.
+            return duration_secs(entry.last_modified) <= duration_secs(since);
+        }
+    }
+
+    false
+}
+
+fn entry_response(entry: CacheEntry) -> Response {
+    let mut response = Response::new(Body::from(entry.bytes));
+    let headers = response.headers_mut();
+    if let Ok(value) = entry.content_type.parse() {
+        headers.insert(header::CONTENT_TYPE, value);
+    }
+    if let Ok(value) = entry.etag.parse() {
+        headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = httpdate::fmt_http_date(entry.last_modified).parse() {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+    response
+}
+
+/// Buffer `response`'s body (if it's small enough to be worth caching),
+/// stash it keyed by `fs_path`/`mtime`, and return an equivalent response
+/// with `ETag` attached for the caller.
+async fn cache_response(cache: &FileCache, fs_path: PathBuf, mtime: SystemTime, response: Response) -> Response {
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| get_mime_type(&fs_path));
+
+    let declared_len = response
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if declared_len.map_or(false, |len| len > MAX_CACHEABLE_BODY) {
+        return response; // too large to be worth buffering
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()), // failed to buffer; skip caching
+    };
+
+    if bytes.len() > MAX_CACHEABLE_BODY {
+        return Response::from_parts(parts, Body::from(bytes)); // too large to be worth caching
+    }
+
+    let etag = format!("\"{:x}-{}\"", duration_secs(mtime), bytes.len());
+    let entry = CacheEntry { bytes: bytes.clone(), content_type, etag: etag.clone(), last_modified: mtime };
+    cache.insert(fs_path, entry);
+    debug!("Cached response ({} bytes, etag {})", bytes.len(), etag);
+
+    let mut response = Response::from_parts(parts, Body::from(bytes));
+    if let Ok(value) = etag.parse() {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
+
+fn duration_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn make_entry(last_modified: SystemTime) -> CacheEntry {
+        CacheEntry {
+            bytes: Bytes::from_static(b"hello"),
+            content_type: "text/plain".to_string(),
+            etag: "\"abc\"".to_string(),
+            last_modified,
+        }
+    }
+
+    #[test]
+    fn test_file_cache_insert_and_get() {
+        let cache = FileCache::new(1024);
+        let mtime = SystemTime::now();
+        cache.insert(PathBuf::from("/a"), make_entry(mtime));
+        assert!(cache.get(&PathBuf::from("/a"), mtime).is_some());
+    }
+
+    #[test]
+    fn test_file_cache_invalidates_on_mtime_change() {
+        let cache = FileCache::new(1024);
+        let mtime = SystemTime::now();
+        cache.insert(PathBuf::from("/a"), make_entry(mtime));
+        assert!(cache.get(&PathBuf::from("/a"), mtime + Duration::from_secs(1)).is_none());
+    }
+
+    #[test]
+    fn test_file_cache_evicts_lru_past_budget() {
+        let cache = FileCache::new(10);
+        let mtime = SystemTime::now();
+        let entry = |etag: &str| CacheEntry {
+            bytes: Bytes::from_static(b"0123456789"),
+            content_type: "text/plain".to_string(),
+            etag: etag.to_string(),
+            last_modified: mtime,
+        };
+
+        cache.insert(PathBuf::from("/a"), entry("\"a\""));
+        cache.insert(PathBuf::from("/b"), entry("\"b\""));
+
+        // "/a" was least-recently-used and got evicted to stay under the budget.
+        assert!(cache.get(&PathBuf::from("/a"), mtime).is_none());
+        assert!(cache.get(&PathBuf::from("/b"), mtime).is_some());
+    }
+
+    #[test]
+    fn test_client_has_fresh_copy_if_none_match() {
+        let entry = make_entry(SystemTime::now());
+        let req = Request::builder()
+            .header(header::IF_NONE_MATCH, entry.etag.clone())
+            .body(Body::empty())
+            .unwrap();
+        assert!(client_has_fresh_copy(&req, &entry));
+    }
+
+    #[test]
+    fn test_client_has_fresh_copy_if_modified_since() {
+        let entry = make_entry(SystemTime::now() - Duration::from_secs(60));
+        let req = Request::builder()
+            .header(header::IF_MODIFIED_SINCE, httpdate::fmt_http_date(SystemTime::now()))
+            .body(Body::empty())
+            .unwrap();
+        assert!(client_has_fresh_copy(&req, &entry));
+    }
+
+    #[test]
+    fn test_client_has_fresh_copy_stale_if_modified_since() {
+        let entry = make_entry(SystemTime::now());
+        let req = Request::builder()
+            .header(header::IF_MODIFIED_SINCE, httpdate::fmt_http_date(SystemTime::now() - Duration::from_secs(60)))
+            .body(Body::empty())
+            .unwrap();
+        assert!(!client_has_fresh_copy(&req, &entry));
+    }
+}
@@ -2,7 +2,7 @@
 ///
 /// This module provides functionality for loading and validating
 /// server configuration from command-line arguments and config files.
-use std::{env, fs, path::{Path, PathBuf}, io, error::Error};
+use std::{collections::HashMap, env, fs, path::{Path, PathBuf}, io, error::Error};
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
@@ -39,12 +39,145 @@ pub struct ServerConfig {
     /// Maximum number of port attempts when binding
     #[serde(default = "default_port_attempts")]
     pub max_port_attempts: u8,
+
+    /// TLS serving configuration
+    #[serde(default)]
+    pub tls: TlsConfig,
+
+    /// Bind only to IPv4 (0.0.0.0) instead of the default dual-stack bind
+    #[serde(default)]
+    pub ipv4_only: bool,
+
+    /// Watch `directory` for changes and auto-refresh connected browsers
+    #[serde(default)]
+    pub live_reload: bool,
+
+    /// Path prefix -> upstream base URL reverse-proxy table, e.g.
+    /// `{ "/api" = "http://localhost:4000" }` in `.srv.toml`
+    #[serde(default)]
+    pub proxy: HashMap<String, String>,
+
+    /// Per-glob response metadata overrides (MIME type, cache headers, etc.)
+    #[serde(default)]
+    pub metadata: Vec<MetadataRule>,
+
+    /// Listen on a Unix domain socket at this path instead of a TCP port.
+    /// A leading `@` selects a Linux abstract-namespace socket.
+    #[serde(default)]
+    pub uds_path: Option<String>,
+
+    /// Expose `GET /__srv_search` for grepping file contents/names under
+    /// the served root
+    #[serde(default)]
+    pub enable_search: bool,
+
+    /// Accept PUT/DELETE/MKCOL mutations against the served directory
+    #[serde(default)]
+    pub writable: bool,
+
+    /// In-memory cache budget, in bytes, for hot static files. `0` disables
+    /// the cache.
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+
+    /// Lazily-spawned reverse-proxy target: front a backend dev server
+    /// that gets started on first request and stopped after being idle
+    #[serde(default)]
+    pub lazy_proxy: Option<LazyProxyConfig>,
+}
+
+/// Config for `ServerConfig::lazy_proxy`: an upstream to front and the
+/// command that brings it up when it isn't already listening.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LazyProxyConfig {
+    /// Base URL of the backend to forward requests to, e.g. `http://localhost:4000`
+    pub upstream: String,
+
+    /// Shell command that starts the backend when `upstream` is unreachable
+    pub spawn: String,
+
+    /// Kill the spawned process after this many seconds with no requests
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+}
+
+/// A single `.srv.toml` `[[metadata]]` rule matched against the request
+/// path by glob pattern (e.g. `"*.wasm"`, `"assets/*.map"`).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MetadataRule {
+    /// Glob pattern matched against the request path
+    pub pattern: String,
+
+    /// Override the guessed `Content-Type` for matching files
+    #[serde(default)]
+    pub content_type: Option<String>,
+
+    /// Value for the `Cache-Control` header
+    #[serde(default)]
+    pub cache_control: Option<String>,
+
+    /// Value for the `Content-Language` header
+    #[serde(default)]
+    pub content_language: Option<String>,
+
+    /// Force the browser to download rather than render the file
+    #[serde(default)]
+    pub download: bool,
+
+    /// Arbitrary extra headers to set on matching responses
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// TLS serving options for `ServerConfig`.
+///
+/// When `enabled` is set and no `cert_path`/`key_path` are provided, `srv`
+/// generates a self-signed certificate on the fly.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct TlsConfig {
+    /// Whether to serve over HTTPS instead of plain HTTP
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Path to a PEM-encoded certificate chain
+    #[serde(default)]
+    pub cert_path: Option<String>,
+
+    /// Path to a PEM-encoded private key
+    #[serde(default)]
+    pub key_path: Option<String>,
+}
+
+/// Parsed command-line options. Threaded from `load_config` straight into
+/// `merge_configs` instead of being re-derived from `env::args()` a second
+/// time there, which used to risk the two call sites silently desyncing
+/// whenever a field was added or reordered.
+#[derive(Debug, Clone)]
+struct CliOptions {
+    port: u16,
+    directory: String,
+    /// Whether `port` came from an explicit positional argument (as
+    /// opposed to the default), so file config can still override it.
+    port_specified: bool,
+    tls: TlsConfig,
+    ipv4_only: bool,
+    uds_path: Option<String>,
+    live_reload: bool,
+    enable_search: bool,
+    writable: bool,
+    cache_size: usize,
+    /// Whether `--cache-size`/`--no-cache` was given explicitly, so file
+    /// config can still override the default when neither flag is passed.
+    cache_size_specified: bool,
+    lazy_proxy: Option<LazyProxyConfig>,
 }
 
 fn default_cors() -> bool { true }
 fn default_true() -> bool { true }
 fn default_index() -> String { "index.html".to_string() }
 fn default_port_attempts() -> u8 { 10 }
+fn default_cache_size() -> usize { 4 * 1024 * 1024 }
+fn default_idle_timeout_secs() -> u64 { 300 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
@@ -58,6 +191,16 @@ impl Default for ServerConfig {
             follow_symlinks: true,
             index_file: "index.html".to_string(),
             max_port_attempts: 10,
+            tls: TlsConfig::default(),
+            ipv4_only: false,
+            live_reload: false,
+            proxy: HashMap::new(),
+            metadata: Vec::new(),
+            uds_path: None,
+            enable_search: false,
+            writable: false,
+            cache_size: default_cache_size(),
+            lazy_proxy: None,
         }
     }
 }
@@ -106,43 +249,111 @@ const CONFIG_FILE: &str = ".srv.toml";
 pub fn load_config() -> Result<ServerConfig, Box<dyn Error>> {
     // First try to parse command-line arguments
     let args: Vec<String> = env::args().collect();
-    let (port, dir) = parse_arguments(&args);
-    
+    let cli = parse_arguments(&args);
+
     // Create base config from command-line args
-    let mut config = ServerConfig::new(port, &dir);
-    
+    let mut config = ServerConfig::new(cli.port, &cli.directory);
+    config.tls = cli.tls.clone();
+    config.ipv4_only = cli.ipv4_only;
+    config.uds_path = cli.uds_path.clone().or_else(|| env::var("SRV_UDS").ok());
+    config.live_reload = cli.live_reload;
+    config.enable_search = cli.enable_search;
+    config.writable = cli.writable;
+    config.cache_size = cli.cache_size;
+    config.lazy_proxy = cli.lazy_proxy.clone();
+
     // Try to load from config file
     if let Some(file_config) = load_config_file(&config.directory) {
         // Merge file config with command-line args, with command-line taking precedence
-        merge_configs(&mut config, file_config);
+        merge_configs(&mut config, file_config, &cli);
     }
-    
+
     // Resolve and validate the directory
     config.resolve_directory()?;
-    
+
     Ok(config)
 }
 
-/// Parse command line arguments for port and directory
-fn parse_arguments(args: &[String]) -> (u16, String) {
+/// Parse command line arguments for port, directory, and TLS options.
+///
+/// Recognizes `--tls`, `--cert <path>`, `--key <path>`, `--ipv4-only`,
+/// `--uds <path>`, `--watch`/`--live-reload`, `--search`, `--writable`,
+/// `--cache-size <bytes>`/`--no-cache`, and `--proxy <url> --spawn
+/// "<command>"` (optionally with `--idle-timeout <secs>`) anywhere in the
+/// argument list; the remaining positional arguments are treated as the
+/// port and directory, same as before.
+fn parse_arguments(args: &[String]) -> CliOptions {
     // Default values
     let default_port = 8000;
     let default_dir = ".".to_string();
-    
-    if args.len() <= 1 {
-        // No arguments provided
-        return (default_port, default_dir);
+    let mut tls = TlsConfig::default();
+    let mut ipv4_only = false;
+    let mut uds_path = None;
+    let mut live_reload = false;
+    let mut enable_search = false;
+    let mut writable = false;
+    let mut cache_size = default_cache_size();
+    let mut cache_size_specified = false;
+    let mut lazy_proxy_upstream = None;
+    let mut lazy_proxy_spawn = None;
+    let mut lazy_proxy_idle_timeout = default_idle_timeout_secs();
+    let mut positional = Vec::new();
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--tls" => tls.enabled = true,
+            "--cert" => tls.cert_path = iter.next().cloned(),
+            "--key" => tls.key_path = iter.next().cloned(),
+            "--ipv4-only" => ipv4_only = true,
+            "--uds" => uds_path = iter.next().cloned(),
+            "--watch" | "--live-reload" => live_reload = true,
+            "--search" => enable_search = true,
+            "--writable" => writable = true,
+            "--no-cache" => {
+                cache_size = 0;
+                cache_size_specified = true;
+            }
+            "--cache-size" => {
+                cache_size = iter.next().and_then(|v| v.parse().ok()).unwrap_or(cache_size);
+                cache_size_specified = true;
+            }
+            "--proxy" => lazy_proxy_upstream = iter.next().cloned(),
+            "--spawn" => lazy_proxy_spawn = iter.next().cloned(),
+            "--idle-timeout" => {
+                lazy_proxy_idle_timeout = iter.next().and_then(|v| v.parse().ok()).unwrap_or(lazy_proxy_idle_timeout);
+            }
+            other => positional.push(other.to_string()),
+        }
     }
-    
-    // Check if first argument is a directory or a port
-    let first_arg = &args[1];
-    if let Ok(port) = first_arg.parse::<u16>() {
-        // First arg is a port
-        let dir = if args.len() > 2 { args[2].clone() } else { default_dir };
-        return (port, dir);
-    } else {
-        // First arg is a directory
-        return (default_port, first_arg.clone());
+
+    let lazy_proxy = match (lazy_proxy_upstream, lazy_proxy_spawn) {
+        (Some(upstream), Some(spawn)) => Some(LazyProxyConfig { upstream, spawn, idle_timeout_secs: lazy_proxy_idle_timeout }),
+        _ => None,
+    };
+
+    // First positional argument is either a port or a directory.
+    let (port, directory, port_specified) = match positional.first() {
+        None => (default_port, default_dir, false),
+        Some(first_arg) => match first_arg.parse::<u16>() {
+            Ok(port) => (port, positional.get(1).cloned().unwrap_or(default_dir), true),
+            Err(_) => (default_port, first_arg.clone(), false),
+        },
+    };
+
+    CliOptions {
+        port,
+        directory,
+        port_specified,
+        tls,
+        ipv4_only,
+        uds_path,
+        live_reload,
+        enable_search,
+        writable,
+        cache_size,
+        cache_size_specified,
+        lazy_proxy,
     }
 }
 
@@ -186,22 +397,40 @@ fn load_config_file(directory: &Path) -> Option<ServerConfig> {
     None
 }
 
-/// Merge configurations, with command-line args taking precedence
-fn merge_configs(cmd_config: &mut ServerConfig, file_config: ServerConfig) {
+/// Merge configurations, with command-line args taking precedence. Takes
+/// the `CliOptions` `load_config` already parsed rather than re-parsing
+/// `env::args()` here, so the two call sites can't desync.
+fn merge_configs(cmd_config: &mut ServerConfig, file_config: ServerConfig, cli: &CliOptions) {
     // Only override port if it wasn't explicitly set via command line
-    let args: Vec<String> = env::args().collect();
-    let was_port_specified = args.len() > 1 && args[1].parse::<u16>().is_ok();
-    
-    if !was_port_specified {
+    if !cli.port_specified {
         cmd_config.port = file_config.port;
     }
-    
+
     // Copy other settings
     cmd_config.enable_cors = file_config.enable_cors;
     cmd_config.show_hidden = file_config.show_hidden;
     cmd_config.follow_symlinks = file_config.follow_symlinks;
     cmd_config.index_file = file_config.index_file;
     cmd_config.max_port_attempts = file_config.max_port_attempts;
+
+    // TLS: command-line `--tls`/`--cert`/`--key` take precedence over the file
+    cmd_config.tls = if cli.tls.enabled || cli.tls.cert_path.is_some() || cli.tls.key_path.is_some() {
+        cli.tls.clone()
+    } else {
+        file_config.tls
+    };
+
+    // `--ipv4-only` on the command line always wins; otherwise fall back to the file
+    cmd_config.ipv4_only = cli.ipv4_only || file_config.ipv4_only;
+
+    cmd_config.live_reload = cli.live_reload || file_config.live_reload;
+    cmd_config.enable_search = cli.enable_search || file_config.enable_search;
+    cmd_config.writable = cli.writable || file_config.writable;
+    cmd_config.cache_size = if cli.cache_size_specified { cli.cache_size } else { file_config.cache_size };
+    cmd_config.uds_path = cmd_config.uds_path.clone().or(file_config.uds_path);
+    cmd_config.lazy_proxy = cli.lazy_proxy.clone().or(file_config.lazy_proxy);
+    cmd_config.proxy = file_config.proxy;
+    cmd_config.metadata = file_config.metadata;
 }
 
 /// Save current configuration to a file
@@ -213,3 +442,110 @@ pub fn save_config(config: &ServerConfig, directory: &Path) -> Result<(), Box<dy
     info!("Configuration saved to {}", config_path.display());
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(parts: &[&str]) -> Vec<String> {
+        std::iter::once("srv".to_string())
+            .chain(parts.iter().map(|s| s.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_arguments_defaults() {
+        let cli = parse_arguments(&args(&[]));
+        assert_eq!(cli.port, 8000);
+        assert_eq!(cli.directory, ".");
+        assert!(!cli.port_specified);
+        assert_eq!(cli.cache_size, default_cache_size());
+        assert!(!cli.cache_size_specified);
+    }
+
+    #[test]
+    fn test_parse_arguments_port_and_directory() {
+        let cli = parse_arguments(&args(&["9000", "/srv/www"]));
+        assert_eq!(cli.port, 9000);
+        assert_eq!(cli.directory, "/srv/www");
+        assert!(cli.port_specified);
+    }
+
+    #[test]
+    fn test_parse_arguments_directory_only() {
+        // A non-numeric first positional is a directory, not a port.
+        let cli = parse_arguments(&args(&["/srv/www"]));
+        assert_eq!(cli.port, 8000);
+        assert_eq!(cli.directory, "/srv/www");
+        assert!(!cli.port_specified);
+    }
+
+    #[test]
+    fn test_parse_arguments_flags() {
+        let cli = parse_arguments(&args(&[
+            "--uds", "@srv", "--writable", "--search", "--cache-size", "1024",
+        ]));
+        assert_eq!(cli.uds_path.as_deref(), Some("@srv"));
+        assert!(cli.writable);
+        assert!(cli.enable_search);
+        assert_eq!(cli.cache_size, 1024);
+        assert!(cli.cache_size_specified);
+    }
+
+    #[test]
+    fn test_parse_arguments_no_cache() {
+        let cli = parse_arguments(&args(&["--no-cache"]));
+        assert_eq!(cli.cache_size, 0);
+        assert!(cli.cache_size_specified);
+    }
+
+    #[test]
+    fn test_parse_arguments_lazy_proxy() {
+        let cli = parse_arguments(&args(&[
+            "--proxy", "http://localhost:4000", "--spawn", "npm run dev", "--idle-timeout", "60",
+        ]));
+        let lazy_proxy = cli.lazy_proxy.expect("expected lazy_proxy to be set");
+        assert_eq!(lazy_proxy.upstream, "http://localhost:4000");
+        assert_eq!(lazy_proxy.spawn, "npm run dev");
+        assert_eq!(lazy_proxy.idle_timeout_secs, 60);
+    }
+
+    #[test]
+    fn test_merge_configs_file_fills_unset_cli_fields() {
+        let cli = parse_arguments(&args(&["9000"]));
+        let mut cmd_config = ServerConfig::new(cli.port, &cli.directory);
+        cmd_config.uds_path = cli.uds_path.clone();
+
+        let mut file_config = ServerConfig::new(8000, ".");
+        file_config.uds_path = Some("@from-file".to_string());
+        file_config.writable = true;
+
+        merge_configs(&mut cmd_config, file_config, &cli);
+
+        // Port was specified on the CLI, so the file's port is ignored...
+        assert_eq!(cmd_config.port, 9000);
+        // ...but settings not given on the CLI fall back to the file.
+        assert_eq!(cmd_config.uds_path.as_deref(), Some("@from-file"));
+        assert!(cmd_config.writable);
+    }
+
+    #[test]
+    fn test_merge_configs_cli_takes_precedence() {
+        let cli = parse_arguments(&args(&["--no-cache"]));
+        let mut cmd_config = ServerConfig::new(cli.port, &cli.directory);
+        cmd_config.cache_size = cli.cache_size;
+        cmd_config.uds_path = cli.uds_path.clone().or(Some("@from-cli".to_string()));
+
+        let mut file_config = ServerConfig::new(8000, ".");
+        file_config.cache_size = 999;
+        file_config.uds_path = Some("@from-file".to_string());
+
+        merge_configs(&mut cmd_config, file_config, &cli);
+
+        // `--no-cache` was explicit, so it wins over the file's cache_size.
+        assert_eq!(cmd_config.cache_size, 0);
+        // uds_path was already set on cmd_config (simulating a CLI value), so
+        // the file's uds_path must not clobber it.
+        assert_eq!(cmd_config.uds_path.as_deref(), Some("@from-cli"));
+    }
+}
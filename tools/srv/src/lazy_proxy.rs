@@ -0,0 +1,202 @@
+/// Lazy-spawn reverse proxy with idle shutdown.
+///
+/// Fronts a backend dev server that isn't necessarily running yet: on the
+/// first request, if `upstream` refuses TCP connections, `spawn` is run as
+/// a child process and polled until `upstream` comes up (or a timeout
+/// elapses), then the request is forwarded exactly like `proxy::forward`.
+/// After `idle_timeout` with no requests, the child is killed and the port
+/// freed; the next request respawns it. Useful for multiplexing several
+/// heavyweight dev backends behind one always-on `srv` port.
+use std::{
+    net::{SocketAddr, ToSocketAddrs},
+    sync::Mutex,
+    time::Duration,
+};
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tokio::{
+    net::TcpStream,
+    process::{Child, Command},
+    time::Instant,
+};
+use tracing::{error, info};
+
+use crate::config::LazyProxyConfig;
+use crate::proxy::forward_request;
+
+/// How long to wait for the spawned process to start accepting connections.
+const SPAWN_TIMEOUT: Duration = Duration::from_secs(15);
+/// How often to retry a TCP connect while waiting for the upstream to come up.
+const CONNECT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How often the idle watcher checks whether the child should be stopped.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+struct Shared {
+    client: reqwest::Client,
+    upstream: String,
+    addr: SocketAddr,
+    spawn_cmd: String,
+    idle_timeout: Duration,
+    child: Mutex<Option<Child>>,
+    last_activity: Mutex<Instant>,
+}
+
+/// Shared, cloneable handle used as Axum state for the lazy-proxy route.
+#[derive(Clone)]
+pub struct LazyProxy(std::sync::Arc<Shared>);
+
+impl LazyProxy {
+    pub fn new(config: &LazyProxyConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let addr = resolve_addr(&config.upstream)?;
+        let shared = std::sync::Arc::new(Shared {
+            client: reqwest::Client::new(),
+            upstream: config.upstream.clone(),
+            addr,
+            spawn_cmd: config.spawn.clone(),
+            idle_timeout: Duration::from_secs(config.idle_timeout_secs),
+            child: Mutex::new(None),
+            last_activity: Mutex::new(Instant::now()),
+        });
+
+        let proxy = Self(shared);
+        proxy.spawn_idle_watcher();
+        Ok(proxy)
+    }
+
+    fn touch(&self) {
+        *self.0.last_activity.lock().unwrap() = Instant::now();
+    }
+
+    /// Make sure the upstream is reachable, spawning `spawn_cmd` and
+    /// waiting for it to come up if it isn't.
+    async fn ensure_running(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.is_reachable().await {
+            return Ok(());
+        }
+
+        {
+            let mut child = self.0.child.lock().unwrap();
+            if child.is_none() {
+                info!("Upstream {} unreachable; spawning: {}", self.0.upstream, self.0.spawn_cmd);
+                *child = Some(spawn_child(&self.0.spawn_cmd)?);
+            }
+        }
+
+        self.wait_until_reachable().await
+    }
+
+    async fn is_reachable(&self) -> bool {
+        tokio::time::timeout(CONNECT_POLL_INTERVAL, TcpStream::connect(self.0.addr))
+            .await
+            .map(|result| result.is_ok())
+            .unwrap_or(false)
+    }
+
+    async fn wait_until_reachable(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let deadline = Instant::now() + SPAWN_TIMEOUT;
+        while Instant::now() < deadline {
+            if self.is_reachable().await {
+                info!("Upstream {} is up", self.0.upstream);
+                return Ok(());
+            }
+            tokio::time::sleep(CONNECT_POLL_INTERVAL).await;
+        }
+        Err(format!("upstream {} did not become reachable within {:?}", self.0.upstream, SPAWN_TIMEOUT).into())
+    }
+
+    /// Spawn a background task that kills the child once `idle_timeout`
+    /// passes with no requests.
+    fn spawn_idle_watcher(&self) {
+        let shared = self.0.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
+
+                let mut child = shared.child.lock().unwrap();
+                if child.is_none() {
+                    continue;
+                }
+
+                let idle_for = shared.last_activity.lock().unwrap().elapsed();
+                if idle_for >= shared.idle_timeout {
+                    if let Some(mut running) = child.take() {
+                        info!("Upstream {} idle for {:?}; stopping spawned process", shared.upstream, idle_for);
+                        if let Err(e) = running.start_kill() {
+                            error!("Failed to stop spawned process: {}", e);
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn spawn_child(command: &str) -> Result<Child, Box<dyn std::error::Error>> {
+    Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("failed to spawn {:?}: {}", command, e).into())
+}
+
+fn resolve_addr(upstream: &str) -> Result<SocketAddr, Box<dyn std::error::Error>> {
+    let url = reqwest::Url::parse(upstream)?;
+    let host = url.host_str().ok_or("upstream URL has no host")?;
+    let port = url.port_or_known_default().ok_or("upstream URL has no resolvable port")?;
+
+    (host, port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| format!("could not resolve upstream host {}", host).into())
+}
+
+/// Handler mounted for the lazy-proxy route: ensure the upstream is up,
+/// then forward exactly like a regular reverse-proxy target.
+pub async fn forward(State(proxy): State<LazyProxy>, request: Request<Body>) -> Response {
+    proxy.touch();
+    if let Err(e) = proxy.ensure_running().await {
+        error!("Lazy-proxy failed to bring up upstream: {}", e);
+        return (StatusCode::BAD_GATEWAY, format!("Upstream unavailable: {}", e)).into_response();
+    }
+
+    proxy.touch();
+    forward_request(&proxy.0.client, &proxy.0.upstream, request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_addr_parses_host_and_port() {
+        let addr = resolve_addr("http://localhost:4000").unwrap();
+        assert_eq!(addr.port(), 4000);
+    }
+
+    #[test]
+    fn test_resolve_addr_uses_scheme_default_port() {
+        let addr = resolve_addr("http://localhost").unwrap();
+        assert_eq!(addr.port(), 80);
+    }
+
+    #[test]
+    fn test_resolve_addr_rejects_hostless_url() {
+        assert!(resolve_addr("file:///tmp").is_err());
+    }
+
+    #[test]
+    fn test_new_fails_for_unresolvable_host() {
+        let config = LazyProxyConfig {
+            upstream: "http://this-host-does-not-resolve.invalid".to_string(),
+            spawn: "true".to_string(),
+            idle_timeout_secs: 1,
+        };
+        assert!(LazyProxy::new(&config).is_err());
+    }
+}
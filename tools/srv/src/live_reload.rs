@@ -0,0 +1,241 @@
+/// Live-reload support for the srv HTTP server.
+///
+/// Watches `config.directory` with `notify`, debounces bursts of
+/// create/modify/remove events into a single notification, and broadcasts
+/// it over a `tokio::sync::broadcast` channel. Browsers connect over a
+/// `/__srv_live` WebSocket (an SSE fallback remains at `/__srv_reload`); a
+/// response layer injects a tiny script into HTML responses that opens the
+/// socket and reloads the page on any message. Entirely inert unless
+/// `ServerConfig::live_reload` is set.
+use std::path::Path;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::State,
+    http::header,
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures_util::stream::Stream;
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt as _;
+use tracing::{info, warn};
+
+/// How long the watcher must stay quiet after an event before broadcasting,
+/// so e.g. a save-triggered rewrite of several files reloads once.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+const RELOAD_SCRIPT: &str = r#"<script>
+(function() {
+  function connect() {
+    var ws = new WebSocket((location.protocol === "https:" ? "wss://" : "ws://") + location.host + "/__srv_live");
+    ws.onmessage = function() { location.reload(); };
+    ws.onclose = function() { setTimeout(connect, 1000); };
+  }
+  connect();
+})();
+</script>
+</body>"#;
+
+/// The kind of filesystem change that triggered a reload notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+/// Handle to the live-reload broadcast channel, cloned into request state.
+#[derive(Clone)]
+pub struct ReloadHandle {
+    tx: broadcast::Sender<ChangeKind>,
+}
+
+impl ReloadHandle {
+    /// Start watching `directory` recursively. Events are debounced on a
+    /// background task before being broadcast, so a burst of writes (e.g.
+    /// a build tool rewriting several files) triggers a single reload. The
+    /// returned watcher must be kept alive for the lifetime of the server;
+    /// dropping it (e.g. on shutdown) stops the watch.
+    pub fn new(directory: &Path) -> Result<(Self, notify::RecommendedWatcher), notify::Error> {
+        let (tx, _rx) = broadcast::channel(16);
+        let (raw_tx, raw_rx) = mpsc::unbounded_channel();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            match res {
+                Ok(event) => {
+                    let kind = if event.kind.is_create() {
+                        Some(ChangeKind::Created)
+                    } else if event.kind.is_modify() {
+                        Some(ChangeKind::Modified)
+                    } else if event.kind.is_remove() {
+                        Some(ChangeKind::Removed)
+                    } else {
+                        None
+                    };
+                    if let Some(kind) = kind {
+                        let _ = raw_tx.send(kind);
+                    }
+                }
+                Err(e) => warn!("Live-reload watcher error: {}", e),
+            }
+        })?;
+
+        watcher.watch(directory, RecursiveMode::Recursive)?;
+        info!("Live-reload watching {}", directory.display());
+
+        tokio::spawn(debounce_task(raw_rx, tx.clone()));
+
+        Ok((Self { tx }, watcher))
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<ChangeKind> {
+        self.tx.subscribe()
+    }
+}
+
+/// Coalesce a burst of raw watcher events into one broadcast per quiet
+/// period, broadcasting the last kind seen in the burst.
+async fn debounce_task(mut raw_rx: mpsc::UnboundedReceiver<ChangeKind>, tx: broadcast::Sender<ChangeKind>) {
+    while let Some(mut pending) = raw_rx.recv().await {
+        loop {
+            match tokio::time::timeout(DEBOUNCE, raw_rx.recv()).await {
+                Ok(Some(kind)) => pending = kind,
+                Ok(None) => return,
+                Err(_) => break, // quiet period elapsed
+            }
+        }
+        let _ = tx.send(pending);
+    }
+}
+
+/// `GET /__srv_live` — a WebSocket that sends one text message per reload.
+pub async fn ws_handler(State(handle): State<ReloadHandle>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, handle))
+}
+
+async fn handle_socket(mut socket: WebSocket, handle: ReloadHandle) {
+    let mut rx = handle.subscribe();
+    loop {
+        tokio::select! {
+            msg = rx.recv() => match msg {
+                Ok(_) => {
+                    if socket.send(Message::Text("reload".to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            // Detect the client closing the connection
+            incoming = socket.recv() => {
+                if incoming.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// `GET /__srv_reload` — an SSE fallback for clients that can't use WebSockets.
+pub async fn sse_handler(
+    State(handle): State<ReloadHandle>,
+) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let stream = BroadcastStream::new(handle.subscribe())
+        .filter_map(|msg| msg.ok())
+        .map(|_| Ok(Event::default().data("reload")));
+
+    Sse::new(stream)
+}
+
+/// Inject the reload script before `</body>` in HTML responses. Non-HTML
+/// responses pass through unchanged.
+pub async fn inject_reload_script(response: Response) -> Response {
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|ct| ct.starts_with("text/html"))
+        .unwrap_or(false);
+
+    if !is_html {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+
+    let html = String::from_utf8_lossy(&bytes);
+    let patched = if html.contains("</body>") {
+        html.replacen("</body>", RELOAD_SCRIPT, 1)
+    } else {
+        format!("{}{}", html, RELOAD_SCRIPT)
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(patched)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    async fn body_text(response: Response) -> String {
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_inject_reload_script_before_closing_body() {
+        let response = Response::builder()
+            .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .body(Body::from("<html><body>hi</body></html>"))
+            .unwrap();
+
+        let injected = inject_reload_script(response).await;
+        let text = body_text(injected).await;
+        assert!(text.contains("WebSocket"));
+        assert!(text.find("<script>").unwrap() < text.find("</body>").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_inject_reload_script_appends_when_no_closing_body() {
+        let response = Response::builder()
+            .header(header::CONTENT_TYPE, "text/html")
+            .body(Body::from("<html>no closing tag"))
+            .unwrap();
+
+        let text = body_text(inject_reload_script(response).await).await;
+        assert!(text.ends_with("</body>"));
+    }
+
+    #[tokio::test]
+    async fn test_inject_reload_script_skips_non_html() {
+        let response = Response::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .status(StatusCode::OK)
+            .body(Body::from("{}"))
+            .unwrap();
+
+        let text = body_text(inject_reload_script(response).await).await;
+        assert_eq!(text, "{}");
+    }
+
+    #[tokio::test]
+    async fn test_inject_reload_script_skips_missing_content_type() {
+        let response = Response::new(Body::from("plain"));
+
+        let text = body_text(inject_reload_script(response).await).await;
+        assert_eq!(text, "plain");
+    }
+}
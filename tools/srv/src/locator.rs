@@ -0,0 +1,164 @@
+/// Single-instance locator for the srv HTTP server.
+///
+/// Mirrors the command-server locator pattern: before binding a port,
+/// check for an existing `.srv.lock` in the served directory, probe
+/// whether it still points at a live srv instance (via a capability-style
+/// handshake against `/__srv_ping`), and if so print the existing URL and
+/// exit instead of drifting to the next free port. A stale lock (dead pid
+/// or unreachable port) is treated as reclaimable.
+use std::{fs, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// A known marker returned by `/__srv_ping` so the locator can tell a
+/// live srv instance apart from some unrelated service on the same port.
+const PING_MARKER: &str = "srv-instance-alive";
+
+/// Path to the lock file, relative to the served directory.
+const LOCK_FILE: &str = ".srv.lock";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LockInfo {
+    pid: u32,
+    port: u16,
+    protocol: String,
+}
+
+/// Result of checking for an existing instance in `directory`.
+pub enum ExistingInstance {
+    /// No lock, or a stale one that was reclaimed.
+    None,
+    /// A live instance is already serving this directory.
+    Running { url: String },
+}
+
+/// Check for a live srv instance already serving `directory`. Reclaims
+/// (removes) a stale lock file before returning `None`.
+pub async fn check_existing(directory: &Path) -> ExistingInstance {
+    let lock_path = directory.join(LOCK_FILE);
+
+    let lock_info = match fs::read_to_string(&lock_path) {
+        Ok(content) => match toml::from_str::<LockInfo>(&content) {
+            Ok(info) => info,
+            Err(_) => {
+                warn!("Ignoring unreadable lock file at {}", lock_path.display());
+                let _ = fs::remove_file(&lock_path);
+                return ExistingInstance::None;
+            }
+        },
+        Err(_) => return ExistingInstance::None,
+    };
+
+    if !pid_is_alive(lock_info.pid) || !ping(lock_info.port, &lock_info.protocol).await {
+        info!("Reclaiming stale lock file at {}", lock_path.display());
+        let _ = fs::remove_file(&lock_path);
+        return ExistingInstance::None;
+    }
+
+    ExistingInstance::Running {
+        url: format!("{}://localhost:{}", lock_info.protocol, lock_info.port),
+    }
+}
+
+/// Write the lock file for this process once bound to `port`.
+pub fn write_lock(directory: &Path, port: u16, protocol: &str) -> std::io::Result<()> {
+    let lock_info = LockInfo {
+        pid: std::process::id(),
+        port,
+        protocol: protocol.to_string(),
+    };
+    let content = toml::to_string_pretty(&lock_info)
+        .unwrap_or_default();
+    fs::write(directory.join(LOCK_FILE), content)
+}
+
+/// Remove the lock file on graceful shutdown.
+pub fn remove_lock(directory: &Path) {
+    let _ = fs::remove_file(directory.join(LOCK_FILE));
+}
+
+/// `GET /__srv_ping` — returns the known marker so the locator can
+/// recognize a live srv instance.
+pub async fn ping_handler() -> &'static str {
+    PING_MARKER
+}
+
+/// Probe whether `port` is actually served by a live srv process. Uses
+/// `protocol` (`"http"`/`"https"`) from the lock file to build the probe
+/// URL and, for HTTPS, accepts the self-signed cert `srv` generates —
+/// otherwise a perfectly healthy `--tls` instance would look dead here.
+async fn ping(port: u16, protocol: &str) -> bool {
+    let url = format!("{}://localhost:{}/__srv_ping", protocol, port);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(1))
+        .danger_accept_invalid_certs(true)
+        .build();
+
+    let client = match client {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+
+    match client.get(&url).send().await {
+        Ok(response) => response.text().await.map(|body| body == PING_MARKER).unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+#[cfg(unix)]
+fn pid_is_alive(pid: u32) -> bool {
+    // Signal 0 performs no action but still validates pid existence/permissions.
+    unsafe { libc::kill(pid as i32, 0) == 0 }
+}
+
+#[cfg(not(unix))]
+fn pid_is_alive(_pid: u32) -> bool {
+    // Without a portable way to check, assume alive and let the ping
+    // handshake be the source of truth.
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_check_existing_with_no_lock_file_is_none() {
+        let dir = tempdir().unwrap();
+        assert!(matches!(check_existing(dir.path()).await, ExistingInstance::None));
+    }
+
+    #[tokio::test]
+    async fn test_check_existing_reclaims_dead_pid() {
+        let dir = tempdir().unwrap();
+        // A pid this high is essentially guaranteed not to be running.
+        let lock_info = LockInfo { pid: 999_999, port: 1, protocol: "http".to_string() };
+        fs::write(dir.path().join(LOCK_FILE), toml::to_string_pretty(&lock_info).unwrap()).unwrap();
+
+        assert!(matches!(check_existing(dir.path()).await, ExistingInstance::None));
+        assert!(!dir.path().join(LOCK_FILE).exists(), "stale lock should be removed");
+    }
+
+    #[tokio::test]
+    async fn test_check_existing_reclaims_unreadable_lock() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(LOCK_FILE), "not valid toml").unwrap();
+
+        assert!(matches!(check_existing(dir.path()).await, ExistingInstance::None));
+        assert!(!dir.path().join(LOCK_FILE).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pid_is_alive_for_current_process() {
+        assert!(pid_is_alive(std::process::id()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pid_is_alive_false_for_bogus_pid() {
+        assert!(!pid_is_alive(999_999));
+    }
+}
@@ -0,0 +1,113 @@
+/// Per-pattern response metadata for the srv HTTP server.
+///
+/// `.srv.toml` can declare `[[metadata]]` rules keyed by glob pattern that
+/// override the guessed content type, set caching/language headers, or
+/// force a download. The matching rule (first one whose pattern matches
+/// the request path) is applied in a response layer; when nothing
+/// matches, the content type falls back to `mime_guess`-based detection,
+/// but only if the handler didn't already set one — this runs as a
+/// router-wide layer, so it must not clobber a `Content-Type` that
+/// `autoindex`/`search`/`live_reload` already set correctly.
+use axum::{
+    extract::{Request, State},
+    http::HeaderName,
+    middleware::Next,
+    response::Response,
+};
+use glob::Pattern;
+use tracing::warn;
+
+use crate::config::{MetadataRule, ServerConfig};
+use crate::server::get_mime_type;
+
+/// Middleware: resolve the metadata rule (if any) for the request path and
+/// apply its headers to the response.
+pub async fn apply(State(config): State<ServerConfig>, req: Request, next: Next) -> Response {
+    let path = req.uri().path().to_string();
+    let rule = config.metadata.iter().find(|rule| matches(&rule.pattern, &path));
+
+    let mut response = next.run(req).await;
+
+    match rule {
+        Some(rule) => apply_rule(&mut response, rule),
+        None => apply_guessed_mime_type(&mut response, &path),
+    }
+
+    response
+}
+
+fn matches(pattern: &str, path: &str) -> bool {
+    match Pattern::new(pattern) {
+        Ok(pattern) => pattern.matches(path) || pattern.matches(path.trim_start_matches('/')),
+        Err(e) => {
+            warn!("Invalid metadata glob pattern {:?}: {}", pattern, e);
+            false
+        }
+    }
+}
+
+fn apply_rule(response: &mut Response, rule: &MetadataRule) {
+    let headers = response.headers_mut();
+
+    if let Some(content_type) = &rule.content_type {
+        if let Ok(value) = content_type.parse() {
+            headers.insert(axum::http::header::CONTENT_TYPE, value);
+        }
+    }
+
+    if let Some(cache_control) = &rule.cache_control {
+        if let Ok(value) = cache_control.parse() {
+            headers.insert(axum::http::header::CACHE_CONTROL, value);
+        }
+    }
+
+    if let Some(content_language) = &rule.content_language {
+        if let Ok(value) = content_language.parse() {
+            headers.insert(axum::http::header::CONTENT_LANGUAGE, value);
+        }
+    }
+
+    if rule.download {
+        headers.insert(
+            axum::http::header::CONTENT_DISPOSITION,
+            "attachment".parse().unwrap(),
+        );
+    }
+
+    for (name, value) in &rule.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), value.parse()) {
+            headers.insert(name, value);
+        }
+    }
+}
+
+/// No rule matched: fall back to `mime_guess`-based content-type detection,
+/// but only if the response doesn't already have a `Content-Type` — the
+/// handler (directory listing, search JSON, SSE stream, ...) knows better
+/// than an extension guess off the request path.
+fn apply_guessed_mime_type(response: &mut Response, path: &str) {
+    if response.headers().contains_key(axum::http::header::CONTENT_TYPE) {
+        return;
+    }
+
+    let mime = get_mime_type(std::path::Path::new(path));
+    if let Ok(value) = mime.parse() {
+        response.headers_mut().insert(axum::http::header::CONTENT_TYPE, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_extension_glob() {
+        assert!(matches("*.wasm", "/assets/app.wasm"));
+        assert!(!matches("*.wasm", "/assets/app.js"));
+    }
+
+    #[test]
+    fn test_matches_path_glob() {
+        assert!(matches("downloads/*", "/downloads/report.pdf"));
+    }
+}
@@ -0,0 +1,131 @@
+/// Reverse-proxy support for the srv HTTP server.
+///
+/// Lets a single `srv` instance serve static assets while forwarding
+/// requests under a configured path prefix (e.g. `/api`) to a running
+/// backend, per `ServerConfig::proxy`. Registered ahead of the `ServeDir`
+/// fallback so a matching prefix always wins.
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{StatusCode, Uri},
+    response::{IntoResponse, Response},
+};
+use tracing::{error, info};
+
+/// State for a single proxied prefix: the shared HTTP client and the
+/// upstream base URL to rewrite requests against.
+#[derive(Clone)]
+pub struct ProxyTarget {
+    client: reqwest::Client,
+    upstream: String,
+}
+
+impl ProxyTarget {
+    pub fn new(upstream: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            upstream,
+        }
+    }
+}
+
+/// Forward `request` to `target.upstream`, preserving method, headers,
+/// query string, and body; stream the upstream response back unchanged.
+/// Connection failures surface as `502 Bad Gateway`.
+pub async fn forward(State(target): State<ProxyTarget>, request: Request<Body>) -> Response {
+    forward_request(&target.client, &target.upstream, request).await
+}
+
+/// Shared forwarding logic reused by [`forward`] and `lazy_proxy::forward`:
+/// rewrite `request`'s URI onto `upstream`, send it through `client`, and
+/// stream the response back unchanged. Both directions are streamed rather
+/// than buffered, so a large upload/download doesn't sit in memory and a
+/// backend that never closes its body (SSE, long-poll) doesn't hang this
+/// waiting on the whole thing to arrive first.
+pub(crate) async fn forward_request(client: &reqwest::Client, upstream: &str, request: Request<Body>) -> Response {
+    let (parts, body) = request.into_parts();
+
+    let path_and_query = parts
+        .uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+    let upstream_url = format!("{}{}", upstream.trim_end_matches('/'), path_and_query);
+
+    let upstream_uri: Uri = match upstream_url.parse() {
+        Ok(uri) => uri,
+        Err(e) => {
+            error!("Invalid proxied URL {}: {}", upstream_url, e);
+            return (StatusCode::BAD_GATEWAY, "Invalid upstream URL").into_response();
+        }
+    };
+
+    let mut req_builder = client.request(parts.method.clone(), upstream_uri.to_string());
+
+    for (name, value) in parts.headers.iter() {
+        // Host is rewritten by reqwest based on the upstream URL; skip the original.
+        if name == axum::http::header::HOST {
+            continue;
+        }
+        req_builder = req_builder.header(name, value);
+    }
+
+    let upstream_request = req_builder.body(reqwest::Body::wrap_stream(body)).build();
+    let upstream_request = match upstream_request {
+        Ok(req) => req,
+        Err(e) => {
+            error!("Failed to build proxied request: {}", e);
+            return (StatusCode::BAD_GATEWAY, "Failed to build proxied request").into_response();
+        }
+    };
+
+    info!("Proxying {} {} -> {}", parts.method, parts.uri, upstream_url);
+
+    match client.execute(upstream_request).await {
+        Ok(upstream_response) => {
+            let status = upstream_response.status();
+            let headers = upstream_response.headers().clone();
+            let body = Body::wrap_stream(upstream_response.bytes_stream());
+
+            let mut response = Response::builder().status(status);
+            for (name, value) in headers.iter() {
+                response = response.header(name, value);
+            }
+            response
+                .body(body)
+                .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+        }
+        Err(e) => {
+            error!("Upstream request to {} failed: {}", upstream, e);
+            (StatusCode::BAD_GATEWAY, format!("Upstream connection failed: {}", e)).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_forward_request_unreachable_upstream_is_bad_gateway() {
+        let request = Request::builder()
+            .uri("/api/widgets?limit=5")
+            .body(Body::empty())
+            .unwrap();
+
+        let client = reqwest::Client::new();
+        // No listener on this port, so the request fails at connect time -
+        // this checks that the failure surfaces as a 502, not an actual
+        // round trip or the rewritten URL.
+        let response = forward_request(&client, "http://127.0.0.1:1", request).await;
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn test_forward_request_rejects_unparsable_upstream() {
+        let request = Request::builder().uri("/x").body(Body::empty()).unwrap();
+        let client = reqwest::Client::new();
+        let response = forward_request(&client, "http://[::not-an-address", request).await;
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+}
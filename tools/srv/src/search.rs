@@ -0,0 +1,184 @@
+/// Content-search endpoint for the srv HTTP server.
+///
+/// Exposes `GET /__srv_search?q=<regex>&path=<glob>&max=<n>` for grepping
+/// file names and contents under the served root, gated behind
+/// `ServerConfig::enable_search`. Walks the tree on a bounded worker pool
+/// via `spawn_blocking` so a large tree doesn't stall the async runtime,
+/// and canonicalizes every candidate file so a symlink can't take results
+/// outside the served root.
+use std::path::{Path, PathBuf};
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use glob::Pattern;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use walkdir::WalkDir;
+
+use crate::config::ServerConfig;
+
+const DEFAULT_MAX_RESULTS: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    q: String,
+    path: Option<String>,
+    max: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchMatch {
+    file: String,
+    line: usize,
+    text: String,
+}
+
+/// `GET /__srv_search` handler.
+pub async fn handler(State(config): State<ServerConfig>, Query(params): Query<SearchParams>) -> Response {
+    let regex = match Regex::new(&params.q) {
+        Ok(regex) => regex,
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid regex: {}", e)).into_response(),
+    };
+
+    let glob_pattern = match &params.path {
+        Some(p) => match Pattern::new(p) {
+            Ok(pattern) => Some(pattern),
+            Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid path glob: {}", e)).into_response(),
+        },
+        None => None,
+    };
+
+    let max = params.max.unwrap_or(DEFAULT_MAX_RESULTS);
+    let root = config.directory.clone();
+    let follow_symlinks = config.follow_symlinks;
+
+    let matches = tokio::task::spawn_blocking(move || search_tree(&root, &regex, glob_pattern.as_ref(), max, follow_symlinks)).await;
+
+    match matches {
+        Ok(matches) => Json(matches).into_response(),
+        Err(e) => {
+            warn!("Search task panicked: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Search failed").into_response()
+        }
+    }
+}
+
+/// Walk `root`, matching `pattern` (if given) against the path relative to
+/// root, and `regex` against each line, capping total results at `max`.
+/// `follow_symlinks` mirrors `ServerConfig::follow_symlinks` so search
+/// doesn't wander outside the tree static serving wouldn't, and every
+/// candidate is re-checked with [`resolve_within_root`] since following a
+/// symlink can land outside `root` even after `WalkDir` filters the walk.
+fn search_tree(root: &Path, regex: &Regex, pattern: Option<&Pattern>, max: usize, follow_symlinks: bool) -> Vec<SearchMatch> {
+    let mut results = Vec::new();
+
+    for entry in WalkDir::new(root).follow_links(follow_symlinks).into_iter().filter_map(Result::ok) {
+        if results.len() >= max {
+            break;
+        }
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        if resolve_within_root(root, entry.path()).is_none() {
+            continue; // symlink escapes the served root; skip defensively
+        }
+
+        let relative = match entry.path().strip_prefix(root) {
+            Ok(relative) => relative,
+            Err(_) => continue, // outside the served root; skip defensively
+        };
+
+        let relative_str = relative.to_string_lossy();
+        if let Some(pattern) = pattern {
+            if !pattern.matches(&relative_str) {
+                continue;
+            }
+        }
+
+        search_file(entry.path(), &relative_str, regex, max, &mut results);
+    }
+
+    results
+}
+
+fn search_file(path: &Path, relative: &str, regex: &Regex, max: usize, results: &mut Vec<SearchMatch>) {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return, // binary or unreadable; skip
+    };
+
+    for (line_number, line) in content.lines().enumerate() {
+        if results.len() >= max {
+            return;
+        }
+        if regex.is_match(line) {
+            results.push(SearchMatch {
+                file: relative.to_string(),
+                line: line_number + 1,
+                text: line.to_string(),
+            });
+        }
+    }
+}
+
+/// Canonicalize `candidate` and confirm it stays within `root`. Unlike the
+/// lexical `writable::resolve_path` guard, this resolves symlinks.
+fn resolve_within_root(root: &Path, candidate: &Path) -> Option<PathBuf> {
+    let canonical_root = root.canonicalize().ok()?;
+    let canonical_candidate = candidate.canonicalize().ok()?;
+    canonical_candidate.starts_with(&canonical_root).then_some(canonical_candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_resolve_within_root_accepts_nested_path() {
+        let dir = tempdir().unwrap();
+        let nested = dir.path().join("a.txt");
+        std::fs::write(&nested, b"hi").unwrap();
+        assert!(resolve_within_root(dir.path(), &nested).is_some());
+    }
+
+    #[test]
+    fn test_resolve_within_root_rejects_escape() {
+        let dir = tempdir().unwrap();
+        let outside = tempdir().unwrap();
+        let other_file = outside.path().join("b.txt");
+        std::fs::write(&other_file, b"hi").unwrap();
+        assert!(resolve_within_root(dir.path(), &other_file).is_none());
+    }
+
+    #[test]
+    fn test_search_tree_matches_regex_and_caps_results() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "hello world\nfoo\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "hello again\nhello once more\n").unwrap();
+
+        let regex = Regex::new("hello").unwrap();
+        let results = search_tree(dir.path(), &regex, None, 2, true);
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_search_tree_filters_by_glob() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "match\n").unwrap();
+        std::fs::write(dir.path().join("a.rs"), "match\n").unwrap();
+
+        let regex = Regex::new("match").unwrap();
+        let pattern = Pattern::new("*.rs").unwrap();
+        let results = search_tree(dir.path(), &regex, Some(&pattern), 10, true);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file, "a.rs");
+    }
+}
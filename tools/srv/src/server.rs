@@ -2,44 +2,135 @@
 ///
 /// This module handles starting the HTTP server, finding available ports,
 /// and managing static file serving with enhanced content type detection.
-use std::{net::SocketAddr, path::Path, time::Instant, error::Error};
+use std::{net::{Ipv4Addr, Ipv6Addr, SocketAddr}, path::Path, time::Instant, error::Error, sync::Arc};
 use axum::Router;
-use tokio::net::TcpListener;
+use futures_util::stream::{self, StreamExt};
+use hyper::server::accept::from_stream;
+use socket2::{Domain, Socket, Type};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsAcceptor;
 use tower_http::{
-    services::ServeDir, 
+    services::ServeDir,
     cors::CorsLayer,
     trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer},
 };
-use tracing::{info, warn, Level};
+use tracing::{info, warn, error, Level};
 use crate::config::ServerConfig;
+use crate::live_reload::{self, ReloadHandle};
+use crate::locator::{self, ExistingInstance};
+use crate::tls;
 use crate::utils;
 use mime_guess::from_path;
 
+/// A bound TCP listener or pair of listeners ready to accept connections.
+///
+/// By default srv binds both `0.0.0.0` and `[::]` so it is reachable over
+/// IPv4 and IPv6. When `ServerConfig::ipv4_only` is set, only the v4
+/// listener is bound.
+pub struct BoundListeners {
+    pub port: u16,
+    v4: Option<TcpListener>,
+    v6: Option<TcpListener>,
+}
+
+impl BoundListeners {
+    /// Merge the v4 and v6 listeners (whichever are present) into a single
+    /// stream of accepted connections.
+    fn into_accept_stream(self) -> impl futures_util::Stream<Item = std::io::Result<TcpStream>> {
+        let v4 = self.v4.map(listener_accept_stream);
+        let v6 = self.v6.map(listener_accept_stream);
+
+        match (v4, v6) {
+            (Some(v4), Some(v6)) => stream::select(v4, v6).boxed(),
+            (Some(v4), None) => v4.boxed(),
+            (None, Some(v6)) => v6.boxed(),
+            (None, None) => stream::empty().boxed(),
+        }
+    }
+}
+
+/// Turn a single `TcpListener` into an infinite stream of accepted streams.
+fn listener_accept_stream(listener: TcpListener) -> impl futures_util::Stream<Item = std::io::Result<TcpStream>> {
+    stream::unfold(listener, |listener| async move {
+        match listener.accept().await {
+            Ok((socket, _peer_addr)) => Some((Ok(socket), listener)),
+            Err(e) => {
+                error!("Failed to accept TCP connection: {}", e);
+                Some((Err(e), listener))
+            }
+        }
+    })
+}
+
 /// Start the HTTP server with the provided configuration
 pub async fn run_server(config: ServerConfig) -> Result<(), Box<dyn Error>> {
-    // Find an available port, starting with the requested one
-    let addr = find_available_port(config.port, config.max_port_attempts).await?;
-    
     // Log directory contents for debugging
     utils::log_directory_contents(&config.directory);
-    
+
+    // When live-reload is enabled, start watching the served directory and
+    // keep the watcher alive for the lifetime of the server.
+    let _watcher;
+    let reload_handle = if config.live_reload {
+        let (handle, watcher) = ReloadHandle::new(&config.directory)?;
+        _watcher = Some(watcher);
+        Some(handle)
+    } else {
+        _watcher = None;
+        None
+    };
+
+    // Create router with enhanced static file service
+    let app = create_app(&config, reload_handle);
+
+    // A Unix domain socket listen mode bypasses ports, the locator, and
+    // dual-stack binding entirely.
+    if let Some(uds_path) = config.uds_path.clone() {
+        return run_server_uds(&config, &uds_path, app).await;
+    }
+
+    // If another srv instance is already healthy for this directory, don't
+    // silently drift to the next free port - point the user at it instead.
+    if let ExistingInstance::Running { url } = locator::check_existing(&config.directory).await {
+        println!("srv is already running for this directory at {}", url);
+        info!("Found existing srv instance at {}, exiting", url);
+        return Ok(());
+    }
+
+    // Find an available port, starting with the requested one
+    let listeners = find_available_port(config.port, config.max_port_attempts, config.ipv4_only).await?;
+    let port = listeners.port;
+
     // Get local IP for display
     let local_ip = utils::get_local_ip();
-    
-    // Create router with enhanced static file service
-    let app = create_app(&config);
-    
+
+    let scheme = if config.tls.enabled { "https" } else { "http" };
+    let dual_stack = listeners.v4.is_some() && listeners.v6.is_some();
+
+    if let Err(e) = locator::write_lock(&config.directory, port, scheme) {
+        warn!("Failed to write lock file: {}", e);
+    }
+
+    // Build TLS material up front so the fingerprint can go in the banner
+    let tls_material = if config.tls.enabled {
+        Some(tls::build_tls_config(&config, &local_ip)?)
+    } else {
+        None
+    };
+
     // Print server information with detailed address
     println!("\n=================================================================");
     println!("📂 Serving files from: {}", config.directory.display());
-    println!("🌐 Local URL: http://localhost:{}", addr.port());
-    println!("🔗 Network URL: http://{}:{}", local_ip, addr.port());
-    println!("⚙️  Binding to address: {}", addr);
+    println!("🌐 Local URL: {}://localhost:{}", scheme, port);
+    println!("🔗 Network URL: {}://{}:{}", scheme, local_ip, port);
+    println!("⚙️  Binding to port {} ({})", port, if dual_stack { "IPv4 + IPv6" } else { "IPv4 only" });
+    if let Some(material) = &tls_material {
+        println!("🔒 Certificate fingerprint (SHA-256): {}", material.fingerprint);
+    }
     println!("=================================================================\n");
-    
+
     // Start the server with robust error handling
-    info!("Starting server on {}", addr);
-    
+    info!("Starting server on port {}", port);
+
     // Set up periodic status reports
     let start_time = Instant::now();
     let _status_task = tokio::spawn(async move {
@@ -49,50 +140,128 @@ pub async fn run_server(config: ServerConfig) -> Result<(), Box<dyn Error>> {
             info!("Server status: Running for {:?}", uptime);
         }
     });
-    
+
     // Print success before server start
     println!("Server starting! Press Ctrl+C to stop.");
-    
-    // Use the classic axum/hyper bind method for 0.6.18
-    info!("Binding server to address: {}", addr);
-    axum::Server::bind(&addr)
+
+    let serve_result = if let Some(material) = tls_material {
+        serve_tls(listeners, app, material.server_config).await
+    } else {
+        axum::Server::builder(from_stream(listeners.into_accept_stream()))
+            .serve(app.into_make_service())
+            .await
+            .map_err(|e| e.into())
+    };
+
+    locator::remove_lock(&config.directory);
+    serve_result?;
+
+    println!("Server shutdown complete.");
+
+    Ok(())
+}
+
+/// Serve the app over a Unix domain socket instead of TCP.
+async fn run_server_uds(config: &ServerConfig, uds_path: &str, app: Router) -> Result<(), Box<dyn Error>> {
+    let listener = crate::uds::bind(uds_path)?;
+
+    println!("\n=================================================================");
+    println!("📂 Serving files from: {}", config.directory.display());
+    println!("🔌 Unix socket: {}", crate::uds::display_path(uds_path));
+    println!("=================================================================\n");
+
+    info!("Starting server on Unix socket {}", crate::uds::display_path(uds_path));
+    println!("Server starting! Press Ctrl+C to stop.");
+
+    let stream = stream::unfold(listener, |listener| async move {
+        match listener.accept().await {
+            Ok((socket, _addr)) => Some((Ok(socket), listener)),
+            Err(e) => {
+                error!("Failed to accept Unix socket connection: {}", e);
+                Some((Err(e), listener))
+            }
+        }
+    });
+
+    axum::Server::builder(from_stream(stream))
         .serve(app.into_make_service())
         .await?;
-        
+
+    crate::uds::cleanup(uds_path);
     println!("Server shutdown complete.");
-    
+
     Ok(())
 }
 
-/// Find an available port starting from the requested port.
+/// Serve the app over TLS by wrapping accepted TCP connections in a
+/// `tokio_rustls::TlsAcceptor` before handing them to axum/hyper.
+async fn serve_tls(listeners: BoundListeners, app: Router, tls_config: Arc<rustls::ServerConfig>) -> Result<(), Box<dyn Error>> {
+    let acceptor = TlsAcceptor::from(tls_config);
+    let accept_stream = listeners.into_accept_stream();
+
+    let stream = accept_stream.then(move |conn| {
+        let acceptor = acceptor.clone();
+        async move {
+            let socket = conn?;
+            match acceptor.accept(socket).await {
+                Ok(tls_stream) => Ok(tls_stream),
+                Err(e) => {
+                    warn!("TLS handshake failed: {}", e);
+                    Err(e)
+                }
+            }
+        }
+    });
+
+    axum::Server::builder(from_stream(stream))
+        .serve(app.into_make_service())
+        .await?;
+
+    Ok(())
+}
+
+/// Bind listener(s) for an available port starting from the requested one.
 ///
-/// This function uses a more reliable approach to find available ports
-/// without leaving listeners hanging.
+/// Binds both `0.0.0.0` and `[::]` by default so the server is reachable
+/// over IPv4 and IPv6 simultaneously; a port is only considered available
+/// once both families bind successfully (unless `ipv4_only` is set). The
+/// IPv6 socket is bound with `IPV6_V6ONLY` so it doesn't race the
+/// dedicated IPv4 socket for the same port.
 ///
 /// # Returns
-/// A `SocketAddr` with an available port
-async fn find_available_port(starting_port: u16, max_attempts: u8) -> Result<SocketAddr, Box<dyn Error>> {
+/// A `BoundListeners` holding the accepted listener(s) for the chosen port
+async fn find_available_port(starting_port: u16, max_attempts: u8, ipv4_only: bool) -> Result<BoundListeners, Box<dyn Error>> {
     let mut port = starting_port;
-    
+
     for attempt in 0..max_attempts {
-        // Try to bind to all interfaces (0.0.0.0)
-        let addr = SocketAddr::from(([0, 0, 0, 0], port));
-        
-        match TcpListener::bind(addr).await {
-            // Port is available
-            Ok(listener) => {
-                // We got a valid listener, so the port is available
-                // We need to drop the listener to release the port for our actual server
-                drop(listener);
-                
+        let v4_addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, port));
+
+        let v4 = match TcpListener::bind(v4_addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Port {} is in use on IPv4 ({}), trying next port", port, e);
+                port += 1;
+                continue;
+            }
+        };
+
+        if ipv4_only {
+            if attempt > 0 {
+                info!("Port {} is in use, using port {} instead", starting_port, port);
+            }
+            return Ok(BoundListeners { port, v4: Some(v4), v6: None });
+        }
+
+        match bind_v6_only(port) {
+            Ok(v6) => {
                 if attempt > 0 {
                     info!("Port {} is in use, using port {} instead", starting_port, port);
                 }
-                return Ok(addr);
-            },
-            // Port is in use - try the next one
+                return Ok(BoundListeners { port, v4: Some(v4), v6: Some(v6) });
+            }
             Err(e) => {
-                warn!("Port {} is in use ({}), trying next port", port, e);
+                warn!("Port {} is in use on IPv6 ({}), trying next port", port, e);
+                drop(v4);
                 port += 1;
             }
         }
@@ -102,25 +271,124 @@ async fn find_available_port(starting_port: u16, max_attempts: u8) -> Result<Soc
     Err(format!("Could not find available port after {} attempts", max_attempts).into())
 }
 
+/// Bind `[::]:<port>` as a v6-only socket (via `socket2`) and hand it back
+/// as a Tokio `TcpListener`. `IPV6_V6ONLY` is set explicitly so this socket
+/// only serves IPv6 traffic, leaving the IPv4 listener to own `0.0.0.0`.
+fn bind_v6_only(port: u16) -> std::io::Result<TcpListener> {
+    let socket = Socket::new(Domain::IPV6, Type::STREAM, None)?;
+    socket.set_only_v6(true)?;
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+
+    let addr: SocketAddr = SocketAddr::from((Ipv6Addr::UNSPECIFIED, port));
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+
+    TcpListener::from_std(socket.into())
+}
+
 /// Create the application router with all middleware and handlers
-fn create_app(config: &ServerConfig) -> Router {
+fn create_app(config: &ServerConfig, reload_handle: Option<ReloadHandle>) -> Router {
     // Create a ServeDir service with enhanced configuration
     let serve_dir = create_serve_dir(config);
-    
-    // Build the router with all middleware
-    Router::new()
-        .fallback_service(serve_dir)
+    let autoindex_state = crate::autoindex::AutoindexState {
+        config: config.clone(),
+        serve_dir,
+    };
+
+    let mut base_router = Router::new()
+        .route("/__srv_ping", axum::routing::get(crate::locator::ping_handler))
+        .fallback(crate::autoindex::serve);
+
+    // Writable mutations share `autoindex::serve`'s state and are
+    // registered on the same `"/*path"` pattern, with that handler wired
+    // in as the `MethodRouter` fallback — otherwise registering PUT/
+    // DELETE/POST here would make GET/HEAD 405 instead of falling
+    // through to static serving.
+    if config.writable {
+        base_router = base_router.route(
+            "/*path",
+            axum::routing::put(crate::writable::put)
+                .delete(crate::writable::delete)
+                .post(crate::writable::mkcol)
+                .fallback(crate::autoindex::serve),
+        );
+    }
+
+    let mut app = base_router.with_state(autoindex_state);
+
+    if config.enable_search {
+        let search_router = Router::new()
+            .route("/__srv_search", axum::routing::get(crate::search::handler))
+            .with_state(config.clone());
+        app = app.merge(search_router);
+    }
+
+    // Live-reload: mount the WebSocket and SSE routes and inject the reload script into
+    // HTML responses. Leaves static-serving behavior unchanged when off.
+    if let Some(handle) = reload_handle {
+        let reload_router = Router::new()
+            .route("/__srv_live", axum::routing::get(live_reload::ws_handler))
+            .route("/__srv_reload", axum::routing::get(live_reload::sse_handler))
+            .with_state(handle);
+
+        app = app
+            .merge(reload_router)
+            .layer(axum::middleware::map_response(live_reload::inject_reload_script));
+    }
+
+    // Lazy-spawn reverse proxy: fronts a backend dev server started on
+    // demand, taking over every route ahead of the static file service.
+    if let Some(lazy_proxy_config) = &config.lazy_proxy {
+        match crate::lazy_proxy::LazyProxy::new(lazy_proxy_config) {
+            Ok(lazy_proxy) => {
+                let lazy_proxy_router = Router::new()
+                    .route("/", axum::routing::any(crate::lazy_proxy::forward))
+                    .route("/*rest", axum::routing::any(crate::lazy_proxy::forward))
+                    .with_state(lazy_proxy);
+                app = app.merge(lazy_proxy_router);
+            }
+            Err(e) => error!("Failed to set up lazy-proxy for {}: {}", lazy_proxy_config.upstream, e),
+        }
+    }
+
+    // Reverse-proxy: forward configured path prefixes to their upstream
+    // before anything falls through to the static file service.
+    for (prefix, upstream) in &config.proxy {
+        let target = crate::proxy::ProxyTarget::new(upstream.clone());
+        let trimmed = prefix.trim_end_matches('/');
+        // A prefix of "/" trims to "", and axum's `Router::route` panics on
+        // a pattern without a leading "/" - treat that as proxying
+        // everything rather than crashing the server at startup.
+        let exact = if trimmed.is_empty() { "/".to_string() } else { trimmed.to_string() };
+        let wildcard = if trimmed.is_empty() { "/*rest".to_string() } else { format!("{}/*rest", trimmed) };
+        let proxy_router = Router::new()
+            .route(&exact, axum::routing::any(crate::proxy::forward))
+            .route(&wildcard, axum::routing::any(crate::proxy::forward))
+            .with_state(target);
+        app = app.merge(proxy_router);
+    }
+
+    // Build the router with all middleware. Layers added earlier end up
+    // innermost (closest to the routes); the cache goes in first so a
+    // cache hit still passes back out through tracing, CORS, and the
+    // metadata rules instead of short-circuiting them.
+    app.layer(axum::middleware::from_fn_with_state(
+            crate::cache::CacheState { config: config.clone(), cache: crate::cache::FileCache::new(config.cache_size) },
+            crate::cache::apply,
+        ))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::default().include_headers(true))
                 .on_request(DefaultOnRequest::new().level(Level::INFO))
                 .on_response(DefaultOnResponse::new().level(Level::INFO))
         )
-        .layer(if config.enable_cors { 
-            CorsLayer::permissive() 
-        } else { 
-            CorsLayer::new() 
+        .layer(if config.enable_cors {
+            CorsLayer::permissive()
+        } else {
+            CorsLayer::new()
         })
+        .layer(axum::middleware::from_fn_with_state(config.clone(), crate::metadata::apply))
 }
 
 /// Create a properly configured ServeDir service for serving files
@@ -140,8 +408,7 @@ fn create_serve_dir(config: &ServerConfig) -> ServeDir {
 }
 
 /// Get enhanced MIME type for a file
-#[allow(dead_code)]
-fn get_mime_type(path: &Path) -> String {
+pub(crate) fn get_mime_type(path: &Path) -> String {
     // Use mime_guess for better MIME type detection
     let mime = from_path(path).first_or_octet_stream();
     
@@ -174,12 +441,22 @@ mod tests {
     async fn test_find_available_port() {
         // Test that an available port is found
         let port = 9000;
-        let result = find_available_port(port, 5).await;
+        let result = find_available_port(port, 5, true).await;
         assert!(result.is_ok());
-        
+
         // The port should be available and equal to what we requested
-        let addr = result.unwrap();
-        assert_eq!(addr.port(), port);
+        let listeners = result.unwrap();
+        assert_eq!(listeners.port, port);
+    }
+
+    #[tokio::test]
+    async fn test_find_available_port_dual_stack() {
+        // With ipv4_only off, both families should be bound
+        let port = 9005;
+        let listeners = find_available_port(port, 5, false).await.unwrap();
+        assert_eq!(listeners.port, port);
+        assert!(listeners.v4.is_some());
+        assert!(listeners.v6.is_some());
     }
 
     #[tokio::test]
@@ -188,17 +465,17 @@ mod tests {
         let port = 9001;
         let addr = SocketAddr::from(([127, 0, 0, 1], port));
         let _socket = StdTcpListener::bind(addr).expect("Failed to bind to port for test");
-        
+
         // Now try to find an available port, starting at the occupied one
-        let result = find_available_port(port, 5).await;
+        let result = find_available_port(port, 5, true).await;
         assert!(result.is_ok());
-        
+
         // We should get a different port than the one we requested
-        let new_addr = result.unwrap();
-        assert_ne!(new_addr.port(), port);
-        
+        let listeners = result.unwrap();
+        assert_ne!(listeners.port, port);
+
         // The new port should be the next one up (port + 1)
-        assert_eq!(new_addr.port(), port + 1);
+        assert_eq!(listeners.port, port + 1);
     }
 
     #[tokio::test]
@@ -211,7 +488,7 @@ mod tests {
         config.resolve_directory().unwrap();
         
         // Just test that app creation doesn't panic
-        let _app = create_app(&config);
+        let _app = create_app(&config, None);
     }
 
     #[tokio::test]
@@ -0,0 +1,128 @@
+/// TLS support for the srv HTTP server.
+///
+/// This module builds a `rustls::ServerConfig` either from a user-supplied
+/// PEM cert/key pair or, when none is configured, from a freshly generated
+/// self-signed certificate covering `localhost`, `127.0.0.1`, and the
+/// machine's detected LAN address.
+use std::{fs::File, io::BufReader, sync::Arc};
+
+use rustls_pemfile::{certs, pkcs8_private_keys};
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::config::ServerConfig;
+
+/// The built `rustls::ServerConfig` plus the leaf certificate's SHA-256
+/// fingerprint, so the startup banner can show users something to verify
+/// a self-signed cert against.
+pub struct TlsMaterial {
+    pub server_config: Arc<rustls::ServerConfig>,
+    pub fingerprint: String,
+}
+
+/// Build the TLS server config for this run, generating a self-signed
+/// certificate when no cert/key paths were configured.
+pub fn build_tls_config(config: &ServerConfig, local_ip: &str) -> Result<TlsMaterial, Box<dyn std::error::Error>> {
+    let (cert_chain, key) = match (&config.tls.cert_path, &config.tls.key_path) {
+        (Some(cert_path), Some(key_path)) => load_pem_cert(cert_path, key_path)?,
+        _ => generate_self_signed_cert(local_ip)?,
+    };
+
+    let fingerprint = sha256_fingerprint(&cert_chain[0].0);
+
+    let mut tls_config = rustls::ServerConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_no_client_auth()
+        .with_single_cert(cert_chain, key)?;
+
+    tls_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(TlsMaterial {
+        server_config: Arc::new(tls_config),
+        fingerprint,
+    })
+}
+
+/// Format the SHA-256 fingerprint of a DER certificate as colon-separated
+/// uppercase hex, e.g. `AB:CD:EF:...`, the conventional browser display form.
+fn sha256_fingerprint(cert_der: &[u8]) -> String {
+    let digest = Sha256::digest(cert_der);
+    digest
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Load a PEM-encoded certificate chain and private key from disk.
+fn load_pem_cert(
+    cert_path: &str,
+    key_path: &str,
+) -> Result<(Vec<rustls::Certificate>, rustls::PrivateKey), Box<dyn std::error::Error>> {
+    let cert_file = &mut BufReader::new(File::open(cert_path)?);
+    let cert_chain = certs(cert_file)?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+
+    let key_file = &mut BufReader::new(File::open(key_path)?);
+    let mut keys = pkcs8_private_keys(key_file)?;
+    let key = keys
+        .pop()
+        .ok_or("No private key found in key file")?;
+
+    info!("Loaded TLS certificate from {} and key from {}", cert_path, key_path);
+    Ok((cert_chain, rustls::PrivateKey(key)))
+}
+
+/// Generate an in-memory self-signed certificate valid for localhost,
+/// 127.0.0.1, and the detected LAN address.
+fn generate_self_signed_cert(
+    local_ip: &str,
+) -> Result<(Vec<rustls::Certificate>, rustls::PrivateKey), Box<dyn std::error::Error>> {
+    let mut subject_alt_names = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+    if local_ip != "localhost" && !subject_alt_names.contains(&local_ip.to_string()) {
+        subject_alt_names.push(local_ip.to_string());
+    }
+
+    info!("Generating self-signed TLS certificate for {:?}", subject_alt_names);
+
+    let cert = rcgen::generate_simple_self_signed(subject_alt_names)?;
+    let cert_der = cert.serialize_der()?;
+    let key_der = cert.serialize_private_key_der();
+
+    Ok((vec![rustls::Certificate(cert_der)], rustls::PrivateKey(key_der)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_self_signed_cert_produces_usable_cert_and_key() {
+        let (chain, key) = generate_self_signed_cert("127.0.0.1").unwrap();
+        assert_eq!(chain.len(), 1);
+        assert!(!chain[0].0.is_empty());
+        assert!(!key.0.is_empty());
+    }
+
+    #[test]
+    fn test_sha256_fingerprint_is_stable_and_colon_separated() {
+        let (chain, _) = generate_self_signed_cert("127.0.0.1").unwrap();
+        let fingerprint = sha256_fingerprint(&chain[0].0);
+        assert_eq!(fingerprint, sha256_fingerprint(&chain[0].0));
+        assert!(fingerprint.contains(':'));
+        // SHA-256 is 32 bytes, formatted as 32 two-digit hex groups joined by ':'.
+        assert_eq!(fingerprint.split(':').count(), 32);
+    }
+
+    #[test]
+    fn test_build_tls_config_generates_self_signed_when_no_paths_given() {
+        let mut config = ServerConfig::new(8000, ".");
+        config.tls.enabled = true;
+        let material = build_tls_config(&config, "127.0.0.1").unwrap();
+        assert!(!material.fingerprint.is_empty());
+    }
+}
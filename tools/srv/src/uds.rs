@@ -0,0 +1,106 @@
+/// Unix domain socket listen mode for the srv HTTP server.
+///
+/// Selected via `--uds <path>` / `SRV_UDS`. A leading `@` in the path
+/// selects a Linux abstract-namespace socket (no filesystem entry, no
+/// cleanup needed) — the common abstract-socket convention (as used by
+/// systemd), since an actual NUL byte can't survive a CLI arg or env var,
+/// both of which are NUL-terminated C strings at the OS level. Otherwise a
+/// regular filesystem socket is bound, and any stale socket file left
+/// behind by a prior run is removed first.
+use std::os::unix::net::UnixListener as StdUnixListener;
+
+use tokio::net::UnixListener;
+
+/// Bind `path` as a Unix domain socket, choosing the abstract namespace
+/// when `path` starts with `@`.
+pub fn bind(path: &str) -> std::io::Result<UnixListener> {
+    let std_listener = if let Some(name) = path.strip_prefix('@') {
+        bind_abstract(name)?
+    } else {
+        // Remove a stale socket file from a previous, uncleanly-stopped run.
+        let _ = std::fs::remove_file(path);
+        StdUnixListener::bind(path)?
+    };
+
+    std_listener.set_nonblocking(true)?;
+    UnixListener::from_std(std_listener)
+}
+
+#[cfg(target_os = "linux")]
+fn bind_abstract(name: &str) -> std::io::Result<StdUnixListener> {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::net::SocketAddr;
+
+    let addr = SocketAddr::from_abstract_name(name.as_bytes())?;
+    StdUnixListener::bind_addr(&addr)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn bind_abstract(_name: &str) -> std::io::Result<StdUnixListener> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "abstract Unix sockets are only supported on Linux",
+    ))
+}
+
+/// A human-readable form of the socket path for logging/banners.
+pub fn display_path(path: &str) -> String {
+    match path.strip_prefix('@') {
+        Some(name) => format!("@{} (abstract)", name),
+        None => path.to_string(),
+    }
+}
+
+/// Remove the socket file on shutdown. No-op for abstract sockets, which
+/// have no filesystem entry.
+pub fn cleanup(path: &str) {
+    if !path.starts_with('@') {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_path_abstract() {
+        assert_eq!(display_path("@srv"), "@srv (abstract)");
+    }
+
+    #[test]
+    fn test_display_path_filesystem() {
+        assert_eq!(display_path("/tmp/srv.sock"), "/tmp/srv.sock");
+    }
+
+    #[test]
+    fn test_cleanup_is_noop_for_abstract_socket() {
+        // Should not attempt (or fail on) any filesystem removal.
+        cleanup("@srv");
+    }
+
+    #[test]
+    fn test_cleanup_removes_filesystem_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("srv.sock");
+        std::fs::write(&path, b"").unwrap();
+        cleanup(path.to_str().unwrap());
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_bind_filesystem_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("srv.sock");
+        let _listener = bind(path.to_str().unwrap()).unwrap();
+        assert!(path.exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_bind_abstract_socket() {
+        // Unique-ish name so parallel test runs don't collide on the socket.
+        let name = format!("srv-test-{}", std::process::id());
+        let _listener = bind(&format!("@{}", name)).unwrap();
+    }
+}
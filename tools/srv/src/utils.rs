@@ -81,7 +81,6 @@ pub fn get_local_ip() -> String {
 }
 
 /// Calculate human-readable file size
-#[allow(dead_code)]
 pub fn human_readable_size(size: u64) -> String {
     const UNITS: [&str; 6] = ["B", "KB", "MB", "GB", "TB", "PB"];
     let mut size = size as f64;
@@ -100,7 +99,6 @@ pub fn human_readable_size(size: u64) -> String {
 }
 
 /// Get file modification time as ISO 8601 string
-#[allow(dead_code)]
 pub fn get_modification_time(path: &PathBuf) -> String {
     match fs::metadata(path) {
         Ok(metadata) => {
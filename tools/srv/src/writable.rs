@@ -0,0 +1,173 @@
+/// Upload and mutation support for the srv HTTP server.
+///
+/// Gated behind `ServerConfig::writable`, this turns `srv` into a quick
+/// drop target for testing upload clients: `PUT <path>` creates or
+/// overwrites a file, `DELETE <path>` removes a file or empty directory,
+/// and `POST <path>?mkdir` creates a directory. Every mutation is confined
+/// to `config.directory` via strict path normalization and logged through
+/// `tracing`.
+///
+/// These handlers share `AutoindexState` (rather than taking `ServerConfig`
+/// directly) so they can live on the same `"/*path"` route as
+/// `autoindex::serve`, with that handler wired in as the `MethodRouter`
+/// fallback — that keeps `GET`/`HEAD` falling through to static serving
+/// once `PUT`/`DELETE`/`POST` are registered on the same path.
+use std::path::{Path, PathBuf};
+
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::{StatusCode, Uri},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::autoindex::AutoindexState;
+use crate::config::ServerConfig;
+
+#[derive(Debug, Deserialize)]
+pub struct MkdirParams {
+    #[serde(default)]
+    mkdir: bool,
+}
+
+/// `PUT <path>` — create or overwrite a file from the request body.
+/// Returns `201` on create, `204` on overwrite.
+pub async fn put(State(state): State<AutoindexState>, uri: Uri, body: Bytes) -> Response {
+    let config = &state.config;
+    let path = match resolve_path(config, uri.path()) {
+        Ok(path) => path,
+        Err(response) => return response,
+    };
+
+    if path.is_dir() {
+        return (StatusCode::CONFLICT, "Path is a directory").into_response();
+    }
+
+    let existed = path.is_file();
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            return (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create parent directories: {}", e)).into_response();
+        }
+    }
+
+    match std::fs::write(&path, &body) {
+        Ok(()) => {
+            info!("PUT {} ({} bytes){}", path.display(), body.len(), if existed { " [overwrite]" } else { " [create]" });
+            if existed {
+                StatusCode::NO_CONTENT.into_response()
+            } else {
+                StatusCode::CREATED.into_response()
+            }
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write file: {}", e)).into_response(),
+    }
+}
+
+/// `DELETE <path>` — remove a file or empty directory. Returns `204` on
+/// success, `409` if a non-empty directory was targeted.
+pub async fn delete(State(state): State<AutoindexState>, uri: Uri) -> Response {
+    let config = &state.config;
+    let path = match resolve_path(config, uri.path()) {
+        Ok(path) => path,
+        Err(response) => return response,
+    };
+
+    let result = if path.is_dir() {
+        std::fs::remove_dir(&path)
+    } else {
+        std::fs::remove_file(&path)
+    };
+
+    match result {
+        Ok(()) => {
+            info!("DELETE {}", path.display());
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => StatusCode::NOT_FOUND.into_response(),
+        Err(e) if is_directory_not_empty(&e) => (StatusCode::CONFLICT, "Directory is not empty").into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete: {}", e)).into_response(),
+    }
+}
+
+/// `POST <path>?mkdir` — create a directory. Returns `201` on create,
+/// `409` if the path already exists.
+pub async fn mkcol(State(state): State<AutoindexState>, uri: Uri, Query(params): Query<MkdirParams>) -> Response {
+    if !params.mkdir {
+        return (StatusCode::BAD_REQUEST, "Expected ?mkdir").into_response();
+    }
+
+    let config = &state.config;
+    let path = match resolve_path(config, uri.path()) {
+        Ok(path) => path,
+        Err(response) => return response,
+    };
+
+    if path.exists() {
+        return (StatusCode::CONFLICT, "Path already exists").into_response();
+    }
+
+    match std::fs::create_dir_all(&path) {
+        Ok(()) => {
+            info!("MKCOL {}", path.display());
+            StatusCode::CREATED.into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create directory: {}", e)).into_response(),
+    }
+}
+
+fn is_directory_not_empty(e: &std::io::Error) -> bool {
+    matches!(e.raw_os_error(), Some(39) /* ENOTEMPTY on Linux */) || e.kind() == std::io::ErrorKind::Other
+}
+
+/// Resolve `request_path` against `config.directory`, rejecting any path
+/// that would normalize outside of it (e.g. via `..`). Also used by
+/// `autoindex::serve` to guard the directory-listing fallback.
+pub(crate) fn resolve_path(config: &ServerConfig, request_path: &str) -> Result<PathBuf, Response> {
+    let relative = request_path.trim_start_matches('/');
+    let joined = config.directory.join(relative);
+
+    let normalized = normalize(&joined);
+    if !normalized.starts_with(&config.directory) {
+        return Err((StatusCode::FORBIDDEN, "Path escapes the served directory").into_response());
+    }
+
+    Ok(normalized)
+}
+
+/// Lexically normalize `.`/`..` components without touching the
+/// filesystem (the target may not exist yet, e.g. for PUT/MKCOL).
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::ParentDir => {
+                out.pop();
+            }
+            std::path::Component::CurDir => {}
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_path_rejects_traversal() {
+        let config = ServerConfig::new(8000, "/srv/www");
+        let result = resolve_path(&config, "/../etc/passwd");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_resolve_path_accepts_nested_path() {
+        let config = ServerConfig::new(8000, "/srv/www");
+        let result = resolve_path(&config, "/assets/app.js");
+        assert_eq!(result.unwrap(), PathBuf::from("/srv/www/assets/app.js"));
+    }
+}